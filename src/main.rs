@@ -1,10 +1,21 @@
 // cointoss: A Linux command-line tool for generating Bitcoin BIP39 seed phrases.
 //
 // Functions Overview:
-// 1. parse_arguments(): Parses command-line arguments using the clap crate to determine the desired entropy size.
-// 2. prompt_coin_flips(): Guides the user through entering or generating 128 coin tosses for entropy.
-// 3. generate_checksum(): Appends a checksum to the bitstream based on BIP39 standards.
-// 4. bitstream_to_mnemonic(): Converts the final bitstream into a valid mnemonic by mapping bits to the BIP39 wordlist.
+// 1. Args (clap derive): parses --12/--15/.../--24, --seed/--passphrase, --verify, --language,
+//    --shares/--threshold/--combine, --debias, --dice, and --paranoid.
+// 2. prompt_for_coin_flips() / prompt_for_coin_flips_debiased() / prompt_for_dice_rolls():
+//    collect raw entropy from coin flips (optionally Von Neumann-debiased) or d6 dice rolls.
+// 3. flips_to_bitstream(): packs the collected bits into entropy bytes.
+// 4. Mnemonic: holds the raw entropy plus a chosen Wordlist and computes the checksummed words
+//    on demand in its Display impl, instead of eagerly materializing a word vector.
+// 5. mnemonic_to_seed(): derives the 64-byte BIP39 seed via PBKDF2-HMAC-SHA512.
+// 6. parse_mnemonic() / verify_mnemonic(): the inverse path — recover entropy from a typed-in
+//    mnemonic and confirm its checksum, reporting a MnemonicError on anything malformed.
+// 7. Language / Wordlist: select and look up one of the BIP39 wordlists (only English is
+//    embedded; the other languages are rejected at startup; see Language::wordlist).
+// 8. shamir_split() / shamir_combine(): optional Shamir secret sharing (GF(256), not
+//    SLIP39-wordlist-encoded) for backing up the generated entropy across --shares/--combine.
+// 9. paranoid_preflight(): optional air-gap and kernel-RNG checks gated behind --paranoid.
 //
 // This program supports generating entropy for 12, 15, 18, 21, or 24-word BIP39 seed phrases, ensuring
 // compatibility with wallet standards. It includes user-friendly error handling and detailed guidance throughout.
@@ -13,82 +24,11 @@
 //   - Run with the appropriate entropy flag: `--12`, `--15`, etc.
 //   - For help: `--help`
 
-/* 
-## Function Overview for `main.rs`
-
-This section provides a summary of the functions used in this program and their purpose. 
-The functions are ordered to follow the logical flow of the program's workflow.
-
-### 1. `parse_args`
-**Purpose**: Parses command-line arguments to determine the number of seed phrase words (12, 15, 18, 21, or 24) or display the help message. 
-**Details**: Ensures valid input and sets the appropriate number of coin tosses required based on the entropy size.
-
----
-
-### 2. `prompt_for_coin_tosses`
-**Purpose**: Guides the user through entering coin flips (`h` for heads, `t` for tails), randomizing remaining flips, or exiting the program. 
-**Details**: Handles input validation and builds the initial entropy bitstream.
-
----
-
-### 3. `fill_bitstream_with_heads`
-**Purpose**: Fills the bitstream with all heads (`1`) when the user selects the `fill` option.
-**Details**: Used for testing purposes to generate predictable entropy for debugging.
-
----
-
-### 4. `calculate_checksum`
-**Purpose**: Calculates the checksum bits for the given entropy using SHA-256. 
-**Details**: Extracts the first `(ENT / 32)` bits of the hash to append to the bitstream.
-
----
-
-### 5. `append_checksum`
-**Purpose**: Appends the calculated checksum bits to the entropy bitstream.
-**Details**: Ensures the final bitstream conforms to BIP39 standards.
-
----
-
-### 6. `convert_to_bitstream`
-**Purpose**: Converts user-entered coin tosses into a binary bitstream.
-**Details**: Translates coin flips (`h` or `t`) into `1` or `0` bits.
-
----
-
-### 7. `bitstream_to_mnemonic`
-**Purpose**: Divides the final bitstream into 11-bit chunks and maps them to indices in the BIP39 word list.
-**Details**: Constructs the mnemonic phrase and verifies its correctness.
-
----
-
-### 8. `load_wordlist`
-**Purpose**: Loads the BIP39 English word list into memory.
-**Details**: Reads the word list file or hardcoded data and makes it accessible for index mapping.
-
----
-
-### 9. `print_mnemonic`
-**Purpose**: Outputs the final mnemonic phrase to the user.
-**Details**: Formats the mnemonic as a space-separated string for easy copying and verification.
-
----
-
-### 10. `print_help`
-**Purpose**: Displays the usage instructions for the program.
-**Details**: Provides details on how to use the command-line arguments effectively.
-
----
-
-### 11. `main`
-**Purpose**: The entry point of the program that orchestrates the entire workflow.
-**Details**: Calls the above functions in sequence to parse input, generate entropy, calculate the checksum, and produce the final mnemonic phrase.
-
-*/
-
-
 use clap::{Parser, ArgAction};
 use rand::Rng; // For randomizing remaining flips
-use sha2::{Sha256, Digest};
+use sha2::{Sha256, Sha512, Digest};
+use pbkdf2::pbkdf2_hmac;
+use unicode_normalization::UnicodeNormalization;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -117,6 +57,140 @@ struct Args {
     /// Generate a mnemonic with 24 words (256 bits)
     #[arg(long = "24", action = ArgAction::SetTrue)]
     twenty_four: bool,
+
+    /// Also derive and print the 64-byte BIP39 seed (hex) from the mnemonic
+    #[arg(long = "seed", action = ArgAction::SetTrue)]
+    seed: bool,
+
+    /// Optional BIP39 passphrase used when deriving the seed (defaults to empty)
+    #[arg(long = "passphrase", default_value = "")]
+    passphrase: String,
+
+    /// Verify an existing mnemonic instead of generating a new one
+    #[arg(long = "verify")]
+    verify: Option<String>,
+
+    /// BIP39 wordlist language: english, japanese, spanish, french, italian,
+    /// czech, portuguese, korean, chinese-simplified, or chinese-traditional.
+    /// Only english is embedded in this build; the other names are accepted
+    /// by the parser but the program then exits with an error.
+    #[arg(long = "language", default_value = "english")]
+    language: String,
+
+    /// Split the generated entropy into this many Shamir secret-sharing
+    /// shares (GF(256) math only; not SLIP39-wordlist-encoded or
+    /// interoperable with SLIP39 tooling)
+    #[arg(long = "shares")]
+    shares: Option<u8>,
+
+    /// Number of shares required to reconstruct the entropy (with --shares)
+    #[arg(long = "threshold")]
+    threshold: Option<u8>,
+
+    /// Reconstruct entropy from shares instead of generating a mnemonic.
+    /// Pass each share as "index:threshold:hexbytes", separated by commas.
+    #[arg(long = "combine")]
+    combine: Option<String>,
+
+    /// Debias physically-flipped coins with Von Neumann extraction
+    #[arg(long = "debias", action = ArgAction::SetTrue)]
+    debias: bool,
+
+    /// Collect entropy from d6 dice rolls instead of coin flips
+    #[arg(long = "dice", action = ArgAction::SetTrue)]
+    dice: bool,
+
+    /// Refuse to run unless the machine looks offline and the kernel RNG is current
+    #[arg(long = "paranoid", action = ArgAction::SetTrue)]
+    paranoid: bool,
+}
+
+// The official BIP39 languages. Only `English` embeds its 2048-word list at
+// compile time; `wordlist()` returns `None` for the other nine, and callers
+// exit with an error instead of indexing a list that isn't there.
+//
+// Every mnemonic this program renders or verifies therefore uses the English
+// words: `--language` recognizes all ten names, but no other language's words
+// are ever produced or accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    English,
+    Japanese,
+    Spanish,
+    French,
+    Italian,
+    Czech,
+    Portuguese,
+    Korean,
+    ChineseSimplified,
+    ChineseTraditional,
+}
+
+impl Language {
+    fn parse(name: &str) -> Option<Language> {
+        match name {
+            "english" => Some(Language::English),
+            "japanese" => Some(Language::Japanese),
+            "spanish" => Some(Language::Spanish),
+            "french" => Some(Language::French),
+            "italian" => Some(Language::Italian),
+            "czech" => Some(Language::Czech),
+            "portuguese" => Some(Language::Portuguese),
+            "korean" => Some(Language::Korean),
+            "chinese-simplified" => Some(Language::ChineseSimplified),
+            "chinese-traditional" => Some(Language::ChineseTraditional),
+            _ => None,
+        }
+    }
+
+    fn wordlist(&self) -> Option<&'static [&'static str; 2048]> {
+        match self {
+            Language::English => Some(&BIP39_WORDLIST),
+            // These wordlists are not embedded in this build.
+            Language::Japanese
+            | Language::Spanish
+            | Language::French
+            | Language::Italian
+            | Language::Czech
+            | Language::Portuguese
+            | Language::Korean
+            | Language::ChineseSimplified
+            | Language::ChineseTraditional => None,
+        }
+    }
+}
+
+// A loaded BIP39 wordlist for a particular language, exposing both
+// index->word and word->index lookups. `Mnemonic` and the mnemonic importer
+// both take a `Wordlist`, so rendering and verification use whichever list was
+// loaded; only the English list can be loaded (see `Language::wordlist`).
+// NFKD normalization is applied to the mnemonic text during seed derivation.
+#[derive(Clone, Copy)]
+struct Wordlist<'a> {
+    language: Language,
+    words: &'a [&'a str; 2048],
+}
+
+impl<'a> Wordlist<'a> {
+    // Load the wordlist for a language, or `None` for the nine languages whose
+    // lists are not embedded (see `Language::wordlist`); English is the only
+    // one that loads in this build.
+    fn for_language(language: Language) -> Option<Wordlist<'a>> {
+        language.wordlist().map(|words| Wordlist { language, words })
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn word(&self, index: u16) -> &'a str {
+        self.words[index as usize]
+    }
+
+    // Look up a word's 11-bit index via binary search (wordlists are lexicographically sorted).
+    fn index_of(&self, word: &str) -> Option<u16> {
+        self.words.binary_search(&word).ok().map(|i| i as u16)
+    }
 }
 
 
@@ -132,6 +206,43 @@ fn main() {
     // Parse command-line arguments
     let args = Args::parse();
 
+    // Air-gap preflight: since this tool mints real wallet seeds, refuse to
+    // run on a networked machine or a kernel predating the fixed getrandom/RNG
+    // behavior when the user opts into the stricter check.
+    if args.paranoid {
+        paranoid_preflight();
+    }
+
+    // Resolve the requested wordlist language
+    let language = Language::parse(&args.language).unwrap_or_else(|| {
+        println!("Unknown language: {:?}", args.language);
+        std::process::exit(1);
+    });
+    let wordlist = Wordlist::for_language(language).unwrap_or_else(|| {
+        println!("The {:?} wordlist is not implemented in this build (only English is vendored).", language);
+        std::process::exit(1);
+    });
+
+    // Verify mode takes an existing mnemonic and checks it instead of generating one
+    if let Some(phrase) = &args.verify {
+        verify_mnemonic(phrase, &wordlist, args.seed, &args.passphrase);
+        return;
+    }
+
+    // Combine mode reconstructs entropy from a set of Shamir shares
+    if let Some(shares_arg) = &args.combine {
+        let shares = parse_shamir_shares(shares_arg).unwrap_or_else(|error| {
+            println!("{}", error);
+            std::process::exit(1);
+        });
+        let secret = shamir_combine(&shares).unwrap_or_else(|error| {
+            println!("{}", error);
+            std::process::exit(1);
+        });
+        println!("Reconstructed entropy: {}", to_hex(&secret));
+        return;
+    }
+
     // Determine the number of words
     let words = if args.twelve {
         12
@@ -151,24 +262,422 @@ fn main() {
 		let entropy_bits = get_entropy_bits(words);
 
     // Prompt the user for coin flips
-    let coin_flips = prompt_for_coin_flips(entropy_bits);
+    let coin_flips = if args.dice {
+        prompt_for_dice_rolls(entropy_bits)
+    } else if args.debias {
+        prompt_for_coin_flips_debiased(entropy_bits)
+    } else {
+        prompt_for_coin_flips(entropy_bits)
+    };
 
     // Convert flips to bitstream
     let bitstream = flips_to_bitstream(coin_flips);
 
-    // Hash the bitstream
-    let sha256_hash = hash_bitstream(&bitstream);
+    // Optionally split the raw entropy into Shamir secret-sharing shares
+    // (GF(256) math only, not SLIP39-wordlist-encoded; see shamir_split)
+    if let (Some(n), Some(t)) = (args.shares, args.threshold) {
+        let shares = shamir_split(&bitstream, n, t).unwrap_or_else(|error| {
+            println!("{}", error);
+            std::process::exit(1);
+        });
+        for (index, threshold, bytes) in &shares {
+            println!("Share {}: {}:{}:{}", index, index, threshold, to_hex(bytes));
+        }
+    }
 
-    // Extract and append the checksum
-    let final_bitstream = extract_checksum(bitstream, sha256_hash, entropy_bits as usize);
+    // The Mnemonic holds only the raw entropy bytes; words and checksum are
+    // computed on demand whenever it's displayed.
+    let mnemonic = Mnemonic::new(bitstream, wordlist);
 
-    // println!("Line 165: Final bitstream with checksum: {:?}", final_bitstream);
+    // Print the mnemonic
+    println!("Mnemonic: {:?}", mnemonic.to_string());
 
-    // Convert bitstream to mnemonic
-    let mnemonic = bitstream_to_mnemonic(final_bitstream, &BIP39_WORDLIST);
+    // Optionally derive and print the BIP39 seed
+    if args.seed {
+        let words = mnemonic.words();
+        let seed = mnemonic_to_seed(&words, &args.passphrase);
+        println!("Seed: {}", to_hex(&seed));
+    }
+}
 
-    // Print the mnemonic
-    println!("Mnemonic: {:?}", mnemonic.join(" "));
+// Holds BIP39 entropy alongside the wordlist it should be rendered in,
+// computing word indices and the checksum lazily in `Display` (following
+// keyfork's "process entropy on demand" approach) rather than eagerly
+// materializing a bitstream-with-checksum and a separate word vector. This
+// keeps the in-memory footprint down to the sensitive entropy alone and gives
+// a single source of truth for turning entropy into words.
+struct Mnemonic<'a> {
+    entropy: Vec<u8>,
+    wordlist: Wordlist<'a>,
+}
+
+impl<'a> Mnemonic<'a> {
+    fn new(entropy: Vec<u8>, wordlist: Wordlist<'a>) -> Self {
+        Mnemonic { entropy, wordlist }
+    }
+
+    // Expand the entropy into its mnemonic words: append the leading
+    // `entropy.len() * 8 / 32` SHA-256 checksum bits, then map each 11-bit
+    // group to a word.
+    fn words(&self) -> Vec<&'a str> {
+        let checksum_size = self.entropy.len() * 8 / 32;
+        let hash = hash_bitstream(&self.entropy);
+
+        let mut bits = Vec::with_capacity(self.entropy.len() * 8 + checksum_size);
+        for byte in &self.entropy {
+            for bit_index in (0..8).rev() {
+                bits.push((byte >> bit_index) & 1);
+            }
+        }
+        for i in 0..checksum_size {
+            bits.push((hash[i / 8] >> (7 - (i % 8))) & 1);
+        }
+
+        bits.chunks(11)
+            .map(|chunk| {
+                let index: u16 = chunk.iter().fold(0, |acc, &bit| (acc << 1) | bit as u16);
+                self.wordlist.word(index)
+            })
+            .collect()
+    }
+}
+
+impl<'a> std::fmt::Display for Mnemonic<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.words().join(" "))
+    }
+}
+
+// Derive the 512-bit BIP39 seed from a mnemonic and optional passphrase.
+//
+// Per BIP39: the space-joined mnemonic (NFKD-normalized) is the PBKDF2 password,
+// the salt is the literal string "mnemonic" concatenated with the NFKD-normalized
+// passphrase, and the derived key is 64 bytes from PBKDF2-HMAC-SHA512 with 2048
+// iterations. An empty passphrase is valid; the salt is then just "mnemonic".
+fn mnemonic_to_seed(mnemonic: &[&str], passphrase: &str) -> [u8; 64] {
+    let password: String = mnemonic.join(" ").nfkd().collect();
+    let normalized_passphrase: String = passphrase.nfkd().collect();
+    let salt = format!("mnemonic{}", normalized_passphrase);
+
+    let mut seed = [0u8; 64];
+    pbkdf2_hmac::<Sha512>(password.as_bytes(), salt.as_bytes(), 2048, &mut seed);
+    seed
+}
+
+// Render bytes as lowercase hex.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Errors from parsing a mnemonic back into entropy (see `parse_mnemonic`).
+#[derive(Debug)]
+enum MnemonicError {
+    InvalidWordCount(usize),
+    UnknownWord { position: usize, word: String },
+    ChecksumMismatch,
+}
+
+impl std::fmt::Display for MnemonicError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MnemonicError::InvalidWordCount(count) => write!(
+                f,
+                "invalid word count: {} (expected 12, 15, 18, 21, or 24)",
+                count
+            ),
+            MnemonicError::UnknownWord { position, word } => {
+                write!(f, "unknown word at position {}: {:?}", position, word)
+            }
+            MnemonicError::ChecksumMismatch => write!(f, "checksum mismatch"),
+        }
+    }
+}
+
+// Parse a space-separated mnemonic back into its entropy bytes, the inverse of
+// `Mnemonic::words`. Looks each word up in `wordlist` to recover its
+// 11-bit index, concatenates the indices into a bitstream, splits it into the
+// entropy portion and the trailing ENT/32 checksum bits, and recomputes
+// SHA-256 over the entropy to confirm the checksum. Returns the entropy bytes
+// on success, or a `MnemonicError` describing the first problem found.
+fn parse_mnemonic(phrase: &str, wordlist: &Wordlist) -> Result<Vec<u8>, MnemonicError> {
+    let words: Vec<&str> = phrase.split_whitespace().collect();
+    let word_count = words.len();
+
+    let entropy_bits: usize = match word_count {
+        12 => 128,
+        15 => 160,
+        18 => 192,
+        21 => 224,
+        24 => 256,
+        _ => return Err(MnemonicError::InvalidWordCount(word_count)),
+    };
+    let checksum_size = entropy_bits / 32;
+
+    let mut indices = Vec::with_capacity(word_count);
+    for (position, word) in words.iter().enumerate() {
+        match wordlist.index_of(word) {
+            Some(index) => indices.push(index),
+            None => {
+                return Err(MnemonicError::UnknownWord {
+                    position: position + 1,
+                    word: word.to_string(),
+                })
+            }
+        }
+    }
+
+    // Concatenate the 11-bit indices into the full bitstream
+    let mut bits = Vec::with_capacity(word_count * 11);
+    for index in &indices {
+        for bit_index in (0..11).rev() {
+            bits.push(((index >> bit_index) & 1) as u8);
+        }
+    }
+
+    let (entropy_bitstream, checksum_bits) = bits.split_at(entropy_bits);
+
+    let mut entropy_bytes = Vec::with_capacity(entropy_bits / 8);
+    for chunk in entropy_bitstream.chunks(8) {
+        let byte = chunk.iter().fold(0u8, |acc, &bit| (acc << 1) | bit);
+        entropy_bytes.push(byte);
+    }
+
+    let hash = hash_bitstream(&entropy_bytes);
+    let expected_checksum: Vec<u8> = (0..checksum_size)
+        .map(|i| (hash[i / 8] >> (7 - (i % 8))) & 1)
+        .collect();
+
+    if expected_checksum != checksum_bits {
+        return Err(MnemonicError::ChecksumMismatch);
+    }
+
+    Ok(entropy_bytes)
+}
+
+// Validate a user-supplied mnemonic, recover its entropy via `parse_mnemonic`,
+// and report word count, detected entropy size, and the recovered entropy as
+// hex. When `derive_seed` is set, also derives and prints the BIP39 seed for
+// the verified mnemonic, so `--verify --seed` is just as end-to-end useful as
+// generating a fresh one.
+fn verify_mnemonic(phrase: &str, wordlist: &Wordlist, derive_seed: bool, passphrase: &str) {
+    let words: Vec<&str> = phrase.split_whitespace().collect();
+    let word_count = words.len();
+
+    let entropy_bytes = match parse_mnemonic(phrase, wordlist) {
+        Ok(entropy_bytes) => entropy_bytes,
+        Err(error) => {
+            println!("{}", error);
+            std::process::exit(1);
+        }
+    };
+
+    println!("Language: {:?}", wordlist.language());
+    println!("Word count: {}", word_count);
+    println!("Entropy size: {} bits", entropy_bytes.len() * 8);
+    println!("Checksum valid: true");
+    println!("Recovered entropy: {}", to_hex(&entropy_bytes));
+
+    if derive_seed {
+        let seed = mnemonic_to_seed(&words, passphrase);
+        println!("Seed: {}", to_hex(&seed));
+    }
+}
+
+// GF(256) arithmetic using the standard AES reduction polynomial (x^8 + x^4 + x^3 + x + 1, 0x11b).
+//
+// This is plain Shamir secret sharing, NOT SLIP39: shares are presented as
+// "index:threshold:hexbytes" rather than SLIP39 mnemonic words, since the
+// SLIP39 1024-word list hasn't been vendored into this build (see
+// Language::wordlist for the same tradeoff on BIP39 lists). Shares produced
+// here do not interoperate with SLIP39 tooling.
+const SHAMIR_CHECKSUM_LEN: usize = 4;
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut result = 0u8;
+    for _ in 0..8 {
+        if b & 1 != 0 {
+            result ^= a;
+        }
+        let high_bit = a & 0x80;
+        a <<= 1;
+        if high_bit != 0 {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    result
+}
+
+fn gf_pow(a: u8, mut exp: u8) -> u8 {
+    let mut base = a;
+    let mut result = 1u8;
+    while exp > 0 {
+        if exp & 1 != 0 {
+            result = gf_mul(result, base);
+        }
+        base = gf_mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+// Multiplicative inverse in GF(256): a^255 == 1 for every nonzero element, so a^254 == a^-1.
+fn gf_inv(a: u8) -> u8 {
+    assert!(a != 0, "cannot invert zero in GF(256)");
+    gf_pow(a, 254)
+}
+
+// Split `secret` into `n` Shamir shares requiring `threshold` to reconstruct
+// (see the module-level note above: this is not SLIP39).
+//
+// Appends a short checksum of the secret before splitting and records the
+// threshold alongside each share's index, so `shamir_combine` can detect an
+// insufficient or inconsistent set of shares instead of silently
+// reconstructing a plausible-looking wrong secret. For each byte of the
+// resulting payload, builds a random degree-(threshold-1) polynomial whose
+// constant term is that byte, then evaluates it at x = 1..=n (distinct,
+// nonzero x-coordinates, as Shamir's scheme requires).
+fn shamir_split(secret: &[u8], n: u8, threshold: u8) -> Result<Vec<(u8, u8, Vec<u8>)>, String> {
+    if threshold < 1 || threshold > n {
+        return Err(format!(
+            "threshold must be between 1 and the share count (got threshold {} with {} shares)",
+            threshold, n
+        ));
+    }
+    if n as usize > 255 {
+        return Err("this scheme supports at most 255 shares (x-coordinates are a single byte)".to_string());
+    }
+
+    let checksum = &hash_bitstream(secret)[..SHAMIR_CHECKSUM_LEN];
+    let payload: Vec<u8> = secret.iter().chain(checksum).copied().collect();
+
+    let mut rng = rand::thread_rng();
+    let mut shares: Vec<(u8, Vec<u8>)> = (1..=n).map(|x| (x, Vec::with_capacity(payload.len()))).collect();
+
+    for &secret_byte in &payload {
+        // Random coefficients for the degree-(threshold-1) polynomial; coefficients[0] is the secret byte.
+        let mut coefficients = vec![secret_byte];
+        coefficients.extend((1..threshold).map(|_| rng.gen_range(0..=255)));
+
+        for (x, share_bytes) in shares.iter_mut() {
+            let mut y = 0u8;
+            let mut x_power = 1u8;
+            for &coefficient in &coefficients {
+                y ^= gf_mul(coefficient, x_power);
+                x_power = gf_mul(x_power, *x);
+            }
+            share_bytes.push(y);
+        }
+    }
+
+    Ok(shares.into_iter().map(|(x, bytes)| (x, threshold, bytes)).collect())
+}
+
+// Reconstruct the secret from `threshold` or more shares via Lagrange interpolation at x = 0.
+//
+// The reconstructed payload's trailing checksum is verified against a fresh
+// hash of the recovered secret; a mismatch means the supplied shares didn't
+// meet their declared threshold or came from different splits, and is
+// reported as an error rather than returned as a plausible-looking wrong
+// secret.
+fn shamir_combine(shares: &[(u8, u8, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    if shares.is_empty() {
+        return Err("need at least one share to reconstruct".to_string());
+    }
+    let threshold = shares[0].1;
+    if shares.iter().any(|(_, t, _)| *t != threshold) {
+        return Err("shares disagree on their threshold; they don't all come from the same split".to_string());
+    }
+    if (shares.len() as u8) < threshold {
+        return Err(format!(
+            "need at least {} shares to reconstruct, only got {}",
+            threshold,
+            shares.len()
+        ));
+    }
+    let payload_len = shares[0].2.len();
+    if shares.iter().any(|(_, _, bytes)| bytes.len() != payload_len) {
+        return Err("all shares must carry the same number of bytes".to_string());
+    }
+    if payload_len < SHAMIR_CHECKSUM_LEN {
+        return Err("share payload is too short to contain a checksum".to_string());
+    }
+    let mut seen_indices = std::collections::HashSet::new();
+    for (x, _, _) in shares {
+        if !seen_indices.insert(*x) {
+            return Err(format!(
+                "duplicate share index {}; can't combine two shares with the same x-coordinate",
+                x
+            ));
+        }
+    }
+
+    let mut payload = Vec::with_capacity(payload_len);
+    for byte_index in 0..payload_len {
+        let mut byte = 0u8;
+        for (i, (x_i, _, bytes_i)) in shares.iter().enumerate() {
+            // Lagrange basis at x = 0: product of (0 - x_j) / (x_i - x_j) == x_j / (x_i XOR x_j) in GF(2^8).
+            let mut basis = 1u8;
+            for (j, (x_j, _, _)) in shares.iter().enumerate() {
+                if i != j {
+                    let denom = x_i ^ x_j;
+                    basis = gf_mul(basis, gf_mul(*x_j, gf_inv(denom)));
+                }
+            }
+            byte ^= gf_mul(bytes_i[byte_index], basis);
+        }
+        payload.push(byte);
+    }
+
+    let split_at = payload_len - SHAMIR_CHECKSUM_LEN;
+    let (secret, checksum) = payload.split_at(split_at);
+    let expected_checksum = &hash_bitstream(secret)[..SHAMIR_CHECKSUM_LEN];
+    if checksum != expected_checksum {
+        return Err(
+            "checksum mismatch: these shares don't reconstruct a valid secret (wrong threshold, \
+             missing shares, or shares from a different split)"
+                .to_string(),
+        );
+    }
+
+    Ok(secret.to_vec())
+}
+
+// Parse "index:threshold:hexbytes,index:threshold:hexbytes,..." into Shamir shares for --combine.
+fn parse_shamir_shares(spec: &str) -> Result<Vec<(u8, u8, Vec<u8>)>, String> {
+    spec.split(',')
+        .map(|entry| {
+            let entry = entry.trim();
+            let mut fields = entry.splitn(3, ':');
+            let index = fields
+                .next()
+                .ok_or_else(|| format!("malformed share (expected index:threshold:hexbytes): {:?}", entry))?;
+            let threshold = fields
+                .next()
+                .ok_or_else(|| format!("malformed share (expected index:threshold:hexbytes): {:?}", entry))?;
+            let hex = fields
+                .next()
+                .ok_or_else(|| format!("malformed share (expected index:threshold:hexbytes): {:?}", entry))?;
+
+            let index: u8 = index.trim().parse().map_err(|_| format!("invalid share index: {:?}", index))?;
+            let threshold: u8 = threshold
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid share threshold: {:?}", threshold))?;
+            let bytes = from_hex(hex.trim()).ok_or_else(|| format!("invalid share hex bytes: {:?}", hex))?;
+            Ok((index, threshold, bytes))
+        })
+        .collect()
+}
+
+// Parse a lowercase (or uppercase) hex string into bytes.
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
 }
 
 
@@ -252,6 +761,199 @@ fn prompt_for_coin_flips(entropy_bits: u16) -> Vec<u8> {
     flips
 }
 
+// Prompt for coin flips and debias them with Von Neumann extraction.
+//
+// Reads flips in non-overlapping pairs; HH and TT pairs are discarded (they
+// carry no information once the coin is biased), HT emits a `1` bit and TH
+// emits a `0` bit. This cancels any first-order bias as long as successive
+// flips are independent, at the cost of needing more raw flips than
+// `entropy_bits`. Keeps prompting until `entropy_bits` debiased bits are
+// collected, showing a running accepted/discarded count, and supports the
+// same `qf` and `fill` shortcuts as `prompt_for_coin_flips`.
+fn prompt_for_coin_flips_debiased(entropy_bits: u16) -> Vec<u8> {
+    let mut bits = Vec::new();
+    let mut accepted = 0u32;
+    let mut discarded = 0u32;
+    println!(
+        "Von Neumann debiasing: please input coin flips in pairs (h for heads, t for tails)."
+    );
+    println!("HH and TT pairs are discarded; HT yields a 1 bit and TH yields a 0 bit.");
+    println!("Enter 'qf' to quit flipping and randomize the rest.");
+    println!("Enter 'qq' to quit the program.");
+    println!("Enter 'fill' to fill the remaining bits with 1s.");
+
+    while (bits.len() as u16) < entropy_bits {
+        let mut pair = Vec::with_capacity(2);
+        while pair.len() < 2 {
+            let mut input = String::new();
+            print!(
+                "Pair {} flip {} (accepted {}, discarded {}): ",
+                accepted + discarded + 1,
+                pair.len() + 1,
+                accepted,
+                discarded
+            );
+            std::io::Write::flush(&mut std::io::stdout()).unwrap();
+            std::io::stdin().read_line(&mut input).unwrap();
+            let flip = input.trim().to_lowercase();
+
+            match flip.as_str() {
+                "h" => pair.push(1u8),
+                "t" => pair.push(0u8),
+                "qf" => {
+                    println!("Randomizing the remaining bits...");
+                    let remaining = (entropy_bits as usize) - bits.len();
+                    let mut rng = rand::thread_rng();
+                    bits.extend((0..remaining).map(|_| rng.gen_range(0..=1)));
+                    return bits;
+                }
+                "qq" => {
+                    println!("Exiting the program.");
+                    std::process::exit(0);
+                }
+                "fill" => {
+                    println!("Filling the remaining bits with 1s...");
+                    let remaining = (entropy_bits as usize) - bits.len();
+                    bits.extend(vec![1; remaining]);
+                    return bits;
+                }
+                _ => println!("Invalid input. Please enter 'h', 't', 'qf', 'qq', or 'fill'."),
+            }
+        }
+
+        match (pair[0], pair[1]) {
+            (1, 0) => {
+                bits.push(1);
+                accepted += 1;
+            }
+            (0, 1) => {
+                bits.push(0);
+                accepted += 1;
+            }
+            _ => {
+                discarded += 1;
+                println!("Discarded biased pair (HH or TT).");
+            }
+        }
+    }
+
+    bits
+}
+
+// Dice rolls only become safely unbiased once the accumulated range (6^rolls)
+// exceeds the target range by a wide margin, not a bare crossing: right at
+// the crossing point, a large fraction of output residues are still
+// measurably more likely than others under "mod 2^entropy_bits". This many
+// bits of headroom (several bytes) pushes that skew down to cryptographically
+// negligible levels.
+const DICE_SAFETY_MARGIN_BITS: f64 = 64.0;
+
+// How many of `entropy_bits` can currently be safely extracted from
+// `rolls_collected` dice rolls, given `DICE_SAFETY_MARGIN_BITS` of headroom.
+fn safe_dice_bits(rolls_collected: u32, entropy_bits: u16, six_log2: f64) -> u16 {
+    let range_bits = rolls_collected as f64 * six_log2;
+    let safe_bits = (range_bits - DICE_SAFETY_MARGIN_BITS).max(0.0).floor();
+    (safe_bits as u16).min(entropy_bits)
+}
+
+// Prompt for d6 dice rolls and convert them to entropy bits without modulo bias.
+//
+// Each fair d6 roll carries log2(6) ~= 2.585 bits, so rolls are accumulated as
+// base-6 digits into an arbitrary-precision integer (a little-endian byte
+// accumulator, so "mod 2^entropy_bits" is just masking the low bits) rather
+// than being combined one-by-one, which would reintroduce bias. Once the
+// accumulated range (6^rolls) safely exceeds 2^entropy_bits (see
+// `DICE_SAFETY_MARGIN_BITS`), the low `entropy_bits` bits are taken as the
+// result; with the 64-bit margin, about 75 rolls are needed for 128 bits of
+// entropy, about 124 for 256. Supports the same `qf`, `fill`, and `qq` shortcuts as
+// `prompt_for_coin_flips`; like that sibling, `qf`/`fill` only randomize/fill
+// the bits the rolls so far haven't already safely determined.
+fn prompt_for_dice_rolls(entropy_bits: u16) -> Vec<u8> {
+    let mut accumulator: Vec<u8> = vec![0];
+    let mut rolls_collected: u32 = 0;
+    println!("Please input d6 dice rolls (digits 1-6):");
+    println!("Enter 'qf' to quit rolling and randomize the rest.");
+    println!("Enter 'qq' to quit the program.");
+    println!("Enter 'fill' to fill the remaining bits with 1s.");
+
+    let six_log2 = 6f64.log2();
+    loop {
+        let mut input = String::new();
+        print!("Roll {}: ", rolls_collected + 1);
+        std::io::Write::flush(&mut std::io::stdout()).unwrap();
+        std::io::stdin().read_line(&mut input).unwrap();
+        let roll = input.trim();
+
+        match roll {
+            "qf" => {
+                println!("Randomizing the remaining bits...");
+                let known_bits = safe_dice_bits(rolls_collected, entropy_bits, six_log2);
+                let remaining = (entropy_bits - known_bits) as usize;
+                let mut rng = rand::thread_rng();
+                let mut flips: Vec<u8> = (0..remaining).map(|_| rng.gen_range(0..=1)).collect();
+                flips.extend(bignum_low_bits_to_flips(&accumulator, known_bits));
+                return flips;
+            }
+            "qq" => {
+                println!("Exiting the program.");
+                std::process::exit(0);
+            }
+            "fill" => {
+                println!("Filling the remaining bits with 1s...");
+                let known_bits = safe_dice_bits(rolls_collected, entropy_bits, six_log2);
+                let remaining = (entropy_bits - known_bits) as usize;
+                let mut flips = vec![1; remaining];
+                flips.extend(bignum_low_bits_to_flips(&accumulator, known_bits));
+                return flips;
+            }
+            _ => {
+                let digit: u8 = match roll.parse() {
+                    Ok(d) if (1..=6).contains(&d) => d,
+                    _ => {
+                        println!("Invalid input. Please enter a digit 1-6, 'qf', 'qq', or 'fill'.");
+                        continue;
+                    }
+                };
+                bignum_mul_add(&mut accumulator, 6, digit - 1);
+                rolls_collected += 1;
+
+                if safe_dice_bits(rolls_collected, entropy_bits, six_log2) >= entropy_bits {
+                    return bignum_low_bits_to_flips(&accumulator, entropy_bits);
+                }
+            }
+        }
+    }
+}
+
+// Multiply a little-endian bignum by a small multiplier and add a small value, in place.
+fn bignum_mul_add(value: &mut Vec<u8>, multiplier: u16, add: u8) {
+    let mut carry: u32 = add as u32;
+    for byte in value.iter_mut() {
+        let product = *byte as u32 * multiplier as u32 + carry;
+        *byte = (product & 0xff) as u8;
+        carry = product >> 8;
+    }
+    while carry > 0 {
+        value.push((carry & 0xff) as u8);
+        carry >>= 8;
+    }
+}
+
+// Take the low `n_bits` bits of a little-endian bignum, MSB-first, as a flips-style bit vector.
+fn bignum_low_bits_to_flips(value: &[u8], n_bits: u16) -> Vec<u8> {
+    (0..n_bits)
+        .rev()
+        .map(|i| {
+            let byte_index = (i / 8) as usize;
+            let bit_index = i % 8;
+            value
+                .get(byte_index)
+                .map(|byte| (byte >> bit_index) & 1)
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
 
 
 
@@ -291,107 +993,113 @@ fn flips_to_bitstream(flips: Vec<u8>) -> Vec<u8> {
     bitstream
 }
 
-/*
-The extract_checksum function is correctly extracting the checksum bits, but during concatenation, 
-ensure that the entropy bits and checksum are added together without introducing extra bits.
-The checksum should be appended to the entropy to form the final_bitstream:
-*/
-fn extract_checksum(bitstream: Vec<u8>, sha256_hash: Vec<u8>, ent: usize) -> Vec<u8> {
-    let checksum_size = ent / 32; // Number of bits for the checksum
-    println!("Checksum size (in bits): {}", checksum_size);
-
-    // Extract checksum bits
-    let mut checksum_bits = Vec::new();
-    for byte in sha256_hash.iter() {
-        for bit_index in (0..8).rev() {
-            let bit = (byte >> bit_index) & 1;
-            checksum_bits.push(bit);
-            if checksum_bits.len() == checksum_size {
-                break;
-            }
-        }
-        if checksum_bits.len() == checksum_size {
-            break;
-        }
+// Minimum kernel version required to trust the system RNG for --paranoid runs.
+const MIN_KERNEL_VERSION: (u32, u32) = (5, 17);
+
+// Run the --paranoid preflight checks and exit nonzero with an advisory if
+// either fails, so users generating keys on a live, online box (or a kernel
+// predating the fixed getrandom/RNG behavior) are stopped instead of silently
+// producing seeds on a compromised environment.
+fn paranoid_preflight() {
+    println!("Running --paranoid air-gap preflight checks...");
+
+    if let Err(reason) = check_offline() {
+        println!("Preflight failed: this machine does not appear to be air-gapped.");
+        println!("{}", reason);
+        println!("Advisory: disconnect all network interfaces before generating a seed.");
+        std::process::exit(1);
     }
 
-    println!("Extracted checksum bits: {:?}", checksum_bits);
-
-    // Create the final bitstream as a vector of bits
-    let mut final_bitstream = Vec::new();
-    for byte in &bitstream {
-        for bit_index in (0..8).rev() {
-            final_bitstream.push((byte >> bit_index) & 1);
-        }
+    if let Err(reason) = check_kernel_version(MIN_KERNEL_VERSION) {
+        println!("Preflight failed: kernel RNG check did not pass.");
+        println!("{}", reason);
+        println!("Advisory: upgrade to a kernel with the fixed getrandom/RNG behavior before generating a seed.");
+        std::process::exit(1);
     }
-/*
-    println!(
-        "Final bitstream before checksum append: {:?} (bit length: {})",
-        final_bitstream,
-        final_bitstream.len()
-    );
-*/
-    // Append checksum bits
-    final_bitstream.extend(checksum_bits);
-
-    // Assert the final bitstream length
-    assert_eq!(
-        final_bitstream.len(),
-        ent + checksum_size,
-        "Final bitstream must have {} bits",
-        ent + checksum_size
-    );
-/*
-    println!(
-        "Final bitstream with checksum: {:?} (bit length: {})",
-        final_bitstream,
-        final_bitstream.len()
-    );
-*/
-    final_bitstream
-}
-
-
-
-
-
-
 
+    println!("Preflight checks passed: machine appears offline and the kernel RNG is current.");
+}
 
-/*
-The bitstream_to_mnemonic function expects exactly 132 bits for a 12-word mnemonic (128 bits of entropy + 4 bits of checksum). Double-check that the final bitstream passed into this function has this exact length.
-*/
-fn bitstream_to_mnemonic(final_bitstream: Vec<u8>, wordlist: &[&str; 2048]) -> Vec<String> {
-//    println!(
-//        "at line 239 in fn bitstream_to_mnemonic() Final bitstream: {:?} (bit length: {})",
-//        final_bitstream,
-//        final_bitstream.len()
-//    );
+// Check for a default route or an active non-loopback interface, either of
+// which means the machine is reachable over a network.
+fn check_offline() -> Result<(), String> {
+    if let Ok(routes) = std::fs::read_to_string("/proc/net/route") {
+        for line in routes.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // Field 1 is the destination in hex; "00000000" is the default route.
+            if fields.len() > 1 && fields[1] == "00000000" {
+                return Err(format!(
+                    "A default route (IPv4) is configured via interface {:?}.",
+                    fields.first().unwrap_or(&"?")
+                ));
+            }
+        }
+    }
 
-    // Ensure the concatenated bits match the expected 132 bits
-    if final_bitstream.len() != 132 {
-        panic!(
-            "Unexpected bitstream length: {} (expected 132)",
-            final_bitstream.len()
-        );
+    // /proc/net/ipv6_route has no header row; fields are whitespace-separated
+    // with the destination address in field 0 and its prefix length (hex) in
+    // field 1, and the outgoing interface name as the last field. A default
+    // route has prefix length 0 (an all-zero destination with length "00").
+    if let Ok(routes) = std::fs::read_to_string("/proc/net/ipv6_route") {
+        for line in routes.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() > 1 && fields[1] == "00" {
+                return Err(format!(
+                    "A default route (IPv6) is configured via interface {:?}.",
+                    fields.last().unwrap_or(&"?")
+                ));
+            }
+        }
     }
 
-    // Divide into 11-bit chunks
-	// Divide into 11-bit chunks
-let mut mnemonic = Vec::new();
-// println!("Line 382: Final bitstream: {:?}", final_bitstream);
+    if let Ok(devices) = std::fs::read_to_string("/proc/net/dev") {
+        for line in devices.lines().skip(2) {
+            let Some((name, _)) = line.split_once(':') else {
+                continue;
+            };
+            let name = name.trim();
+            if name == "lo" {
+                continue;
+            }
+            let operstate_path = format!("/sys/class/net/{}/operstate", name);
+            if let Ok(state) = std::fs::read_to_string(&operstate_path) {
+                if state.trim() == "up" {
+                    return Err(format!("Active network interface detected: {:?}.", name));
+                }
+            }
+        }
+    }
 
-for chunk_start in (0..132).step_by(11) {
-    let chunk = &final_bitstream[chunk_start..chunk_start + 11];
-    let index: u16 = chunk.iter().fold(0, |acc, &bit| (acc << 1) | bit as u16);
-    assert!(index <= 2047, "Index out of range: {}", index); // Check that the index is valid
-    mnemonic.push(wordlist[index as usize].to_string());
+    Ok(())
 }
-/*
-println!("line 391: Generated mnemonic: {:?}", mnemonic);
-*/
-mnemonic // explicitly return the mnemonic vector
 
+// Parse /proc/version ("Linux version X.Y.Z ...") and confirm it meets the minimum.
+fn check_kernel_version(minimum: (u32, u32)) -> Result<(), String> {
+    let contents = std::fs::read_to_string("/proc/version")
+        .map_err(|e| format!("Unable to read /proc/version: {}", e))?;
+
+    let version_field = contents
+        .split_whitespace()
+        .nth(2)
+        .ok_or_else(|| "Unable to parse /proc/version".to_string())?;
+
+    let mut parts = version_field.split('.');
+    let major: u32 = parts
+        .next()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| format!("Unable to parse kernel major version from {:?}", version_field))?;
+    let minor: u32 = parts
+        .next()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| format!("Unable to parse kernel minor version from {:?}", version_field))?;
+
+    if (major, minor) >= minimum {
+        Ok(())
+    } else {
+        Err(format!(
+            "Kernel {}.{} is older than the minimum required {}.{}.",
+            major, minor, minimum.0, minimum.1
+        ))
+    }
 }
 
-